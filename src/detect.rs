@@ -0,0 +1,100 @@
+use std::process::{Command, Stdio};
+
+/// Returns the first of `candidates` that is actually installed, checked by
+/// invoking it with `--version`.
+fn pick_first_installed<'a>(candidates: &[&'a str]) -> Option<&'a str> {
+    candidates
+        .iter()
+        .find(|bin| {
+            Command::new(bin)
+                .arg("--version")
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .map(|status| status.success())
+                .unwrap_or(false)
+        })
+        .copied()
+}
+
+/// Which tool to search the notes directory with: `rg` is preferred, with
+/// `grep -rnHF` as a fallback when it isn't installed.
+pub enum Searcher {
+    Rg,
+    Grep,
+}
+
+impl Searcher {
+    pub fn detect() -> Result<Searcher, String> {
+        match pick_first_installed(&["rg", "grep"]) {
+            Some("rg") => Ok(Searcher::Rg),
+            Some("grep") => Ok(Searcher::Grep),
+            _ => Err("neither `rg` nor `grep` is installed".to_string()),
+        }
+    }
+
+    /// Builds the search invocation over `targets` (directories and/or
+    /// individual note paths), so tag-filtered search can narrow the
+    /// corpus to a specific set of notes instead of the whole directory.
+    /// Always forces the filename into the output (`--with-filename`/`-H`)
+    /// since both tools otherwise omit it when `targets` is a single file,
+    /// which is the common case for a tag applied to only one note.
+    pub fn command(&self, needle: &str, targets: &[String]) -> Command {
+        match self {
+            Searcher::Rg => {
+                let mut cmd = Command::new("rg");
+                cmd.arg("--line-number")
+                    .arg("--no-heading")
+                    .arg("--with-filename")
+                    .arg("--fixed-strings")
+                    .arg(needle)
+                    .args(targets);
+                cmd
+            }
+            Searcher::Grep => {
+                let mut cmd = Command::new("grep");
+                cmd.arg("-rnHF").arg(needle).args(targets);
+                cmd
+            }
+        }
+    }
+}
+
+/// Which tool to preview a match with: `bat` is preferred for its syntax
+/// highlighting and line-highlighting, with a plain numbered `awk` listing as
+/// a fallback (chosen when `cat` is the installed alternative).
+pub enum Previewer {
+    Bat,
+    Cat,
+}
+
+impl Previewer {
+    pub fn detect() -> Result<Previewer, String> {
+        match pick_first_installed(&["bat", "cat"]) {
+            Some("bat") => Ok(Previewer::Bat),
+            Some("cat") => Ok(Previewer::Cat),
+            _ => Err("neither `bat` nor `cat` is installed".to_string()),
+        }
+    }
+
+    /// Builds the shell snippet the finder runs to preview a match, given
+    /// `{1}` (the file) and `{2}` (the line number) placeholders.
+    pub fn preview_cmd(&self) -> String {
+        let awk_cmd = "(echo {2} | awk '{a=$1-5;if(a<0)a=0;print a}')";
+        match self {
+            Previewer::Bat => {
+                let bat_cmd =
+                    "bat --style=numbers --color=always --highlight-line {2} --line-range";
+                format!("{} {}: {{1}} | head -n10", bat_cmd, awk_cmd)
+            }
+            Previewer::Cat => {
+                let start = "$(echo {2} | awk '{s=$1-5;if(s<0)s=0;print s}')";
+                let end = "$(echo {2} | awk '{print $1+5}')";
+                format!(
+                    "awk -v s={} -v e={} 'NR>=s && NR<=e {{print NR\": \"$0}}' {{1}} | head -n10",
+                    start, end
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Opens `file` at `lineno` in the user's editor, using that editor's
+/// line-jump syntax. The editor is looked up from `edit::get_editor()`/
+/// `$EDITOR` on every call; it is not cached between calls.
+/// Editors without a recognised syntax fall back to `edit::edit_file`,
+/// which opens the file at line 1.
+pub fn open_at_line(file: &str, lineno: &str) -> std::io::Result<()> {
+    let editor = edit::get_editor().expect("Editor should exist");
+    let name = editor.file_name().and_then(|n| n.to_str()).unwrap_or("");
+
+    let args: Vec<String> = match name {
+        "vim" | "nvim" | "nano" => vec![format!("+{}", lineno), file.to_string()],
+        "emacs" | "emacsclient" => vec![format!("+{}", lineno), file.to_string()],
+        "hx" | "helix" => vec![format!("{}:{}", file, lineno)],
+        "code" | "code-insiders" => vec!["--goto".to_string(), format!("{}:{}", file, lineno)],
+        _ => return edit::edit_file(Path::new(file)),
+    };
+
+    Command::new(&editor)
+        .args(&args)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .status()
+        .map(|_| ())
+}
@@ -0,0 +1,152 @@
+use std::io::{Cursor, Write};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+
+/// Which interactive picker backend to hand search results to.
+#[derive(Debug, Clone, Copy)]
+pub enum FinderChoice {
+    Skim,
+    Fzf,
+}
+
+impl FromStr for FinderChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "skim" => Ok(FinderChoice::Skim),
+            "fzf" => Ok(FinderChoice::Fzf),
+            other => Err(format!(
+                "unknown finder `{}`, expected `skim` or `fzf`",
+                other
+            )),
+        }
+    }
+}
+
+impl FinderChoice {
+    /// Resolve the backend to use: an explicit `--finder` flag wins, then
+    /// `NT_FINDER`, and finally the built-in skim picker.
+    pub fn resolve(flag: Option<FinderChoice>) -> FinderChoice {
+        flag.or_else(|| std::env::var("NT_FINDER").ok().and_then(|s| s.parse().ok()))
+            .unwrap_or(FinderChoice::Skim)
+    }
+
+    pub fn finder(self) -> Box<dyn Finder> {
+        match self {
+            FinderChoice::Skim => Box::new(SkimFinder),
+            FinderChoice::Fzf => Box::new(FzfFinder),
+        }
+    }
+}
+
+/// How a finder session ended: a plain accept (enter), or one of the
+/// in-picker actions bound below.
+#[derive(Debug, PartialEq, Eq)]
+pub enum FinderAction {
+    /// Enter — open whatever is selected, then stop searching.
+    Accept,
+    /// `ctrl-n` — create a new note instead of opening a match.
+    NewNote,
+    /// `ctrl-e` — open the highlighted match, then return to the search.
+    Edit,
+}
+
+/// The outcome of a finder session: which action ended it, and whichever
+/// lines were selected at the time.
+pub struct FinderResult {
+    pub action: FinderAction,
+    pub selected: Vec<String>,
+}
+
+/// A pluggable picker: feed it the raw `rg` output and a preview command,
+/// get back the lines the user picked and how they picked them.
+pub trait Finder {
+    fn call(&self, preview_cmd: &str, input: Vec<u8>) -> FinderResult;
+}
+
+/// The embedded skim UI, used by default.
+pub struct SkimFinder;
+
+impl Finder for SkimFinder {
+    fn call(&self, preview_cmd: &str, input: Vec<u8>) -> FinderResult {
+        let options = skim::prelude::SkimOptionsBuilder::default()
+            .height(Some("50%"))
+            .delimiter(Some(":"))
+            .multi(true)
+            .preview(Some(preview_cmd))
+            .bind(vec!["ctrl-n:accept", "ctrl-e:accept"])
+            .build()
+            .unwrap();
+
+        let item_reader = skim::prelude::SkimItemReader::default();
+        let items = item_reader.of_bufread(Cursor::new(input));
+
+        match skim::Skim::run_with(&options, Some(items)) {
+            Some(out) => {
+                let selected = out
+                    .selected_items
+                    .iter()
+                    .map(|item| item.output().to_string())
+                    .collect();
+                let action = match out.final_key {
+                    skim::prelude::Key::Ctrl('n') => FinderAction::NewNote,
+                    skim::prelude::Key::Ctrl('e') => FinderAction::Edit,
+                    _ => FinderAction::Accept,
+                };
+                FinderResult { action, selected }
+            }
+            None => FinderResult {
+                action: FinderAction::Accept,
+                selected: Vec::new(),
+            },
+        }
+    }
+}
+
+/// Shells out to the `fzf` binary, so users with their own bindings/theme
+/// can reuse them instead of the embedded skim UI.
+pub struct FzfFinder;
+
+impl Finder for FzfFinder {
+    fn call(&self, preview_cmd: &str, input: Vec<u8>) -> FinderResult {
+        let mut child = Command::new("fzf")
+            .arg("--delimiter=:")
+            .arg("--multi")
+            .arg(format!("--preview={}", preview_cmd))
+            .arg("--expect=ctrl-n,ctrl-e")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("fzf to be installed");
+
+        child
+            .stdin
+            .take()
+            .expect("fzf stdin to be piped")
+            .write_all(&input)
+            .expect("fzf stdin to accept input");
+
+        let output = child.wait_with_output().expect("fzf to run");
+        let mut lines = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>();
+
+        // `--expect` makes fzf print the key that ended the session as the
+        // first line, ahead of whatever got selected.
+        let action = match lines.first().map(String::as_str) {
+            Some("ctrl-n") => FinderAction::NewNote,
+            Some("ctrl-e") => FinderAction::Edit,
+            _ => FinderAction::Accept,
+        };
+        if !lines.is_empty() {
+            lines.remove(0);
+        }
+
+        FinderResult {
+            action,
+            selected: lines,
+        }
+    }
+}
@@ -1,7 +1,13 @@
+mod detect;
+mod editor;
+mod finder;
+mod tags;
+
 use colored::*;
+use detect::{Previewer, Searcher};
+use finder::{FinderAction, FinderChoice};
 use std::io::BufRead;
-use std::path::Path;
-use std::process::{Command, Stdio};
+use std::path::{Path, PathBuf};
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -12,7 +18,16 @@ enum Nt {
     New { filename: Option<String> },
     /// Search existing notes and open in file for editing
     #[structopt(alias = "s")]
-    Search { needle: String },
+    Search {
+        needle: String,
+        /// Picker backend to use: `skim` (default) or `fzf`. Falls back to
+        /// the `NT_FINDER` env var when omitted.
+        #[structopt(long)]
+        finder: Option<FinderChoice>,
+        /// Restrict the search corpus to notes carrying this tag.
+        #[structopt(long)]
+        tag: Option<String>,
+    },
 }
 
 enum Action {
@@ -20,10 +35,32 @@ enum Action {
     Edited,
 }
 
-fn print_preview(action: Action, fullname: &std::path::PathBuf) {
+fn print_preview(action: Action, dir: &Path, fullname: &std::path::PathBuf) {
+    tags::reindex(dir, fullname);
+
     let file = std::fs::File::open(&fullname).expect("File should exist");
     let reader = std::io::BufReader::new(file);
-    for line in reader.lines() {
+    let mut lines = reader
+        .lines()
+        .map(|line| line.expect("Line should at least exist"))
+        .peekable();
+
+    // Notes are seeded with a YAML front matter block; skip past it so the
+    // preview shows the note's actual content instead of a stray `---`.
+    if lines.peek().map(String::as_str) == Some("---") {
+        lines.next();
+        for line in &mut lines {
+            if line == "---" {
+                break;
+            }
+        }
+        // The seeded header is followed by a blank separator line.
+        while lines.peek().map_or(false, |line| line.is_empty()) {
+            lines.next();
+        }
+    }
+
+    if let Some(line) = lines.next() {
         let action = match action {
             Action::Created => "Created",
             Action::Edited => "Edited",
@@ -39,12 +76,28 @@ fn print_preview(action: Action, fullname: &std::path::PathBuf) {
                 .into_string()
                 .unwrap()
                 .bold(),
-            line.expect("Line should at least exist").bright_black()
+            line.bright_black()
         );
-        return;
     }
 }
 
+/// Writes and opens a new timestamped note, the flow shared by `Nt::New`
+/// and the search UI's `ctrl-n` binding. The note is seeded with a YAML
+/// front matter header so it can carry tags from the moment it's created.
+fn new_note(dir: &Path, filename: Option<String>) -> PathBuf {
+    let now = chrono::Local::now();
+    let (filename, title) = match filename {
+        Some(x) => (format!("{}-{}.md", now.format("%Y%m%d%H%M%S"), x), x),
+        None => (format!("{}.md", now.format("%Y%m%d%H%M%S")), String::new()),
+    };
+    let fullname = dir.join(&filename);
+    let created = now.format("%Y-%m-%d %H:%M:%S").to_string();
+    std::fs::write(&fullname, tags::front_matter(&title, &created)).expect("note to be created");
+    edit::edit_file(&fullname).unwrap();
+    print_preview(Action::Created, dir, &fullname);
+    fullname
+}
+
 fn main() {
     // Ensure that the dir we're working with exists
     let dir = Path::new(&std::env::var("XDG_CONFIG_HOME").unwrap()).join("nt");
@@ -52,80 +105,69 @@ fn main() {
 
     match Nt::from_args() {
         Nt::New { filename } => {
-            let now = chrono::Local::now().format("%Y%m%d%H%M%S");
-            let filename = match filename {
-                Some(x) => format!("{}-{}.md", now, x),
-                None => format!("{}.md", now),
-            };
-            let fullname = dir.join(&filename);
-            edit::edit_file(&fullname).unwrap();
-            print_preview(Action::Created, &fullname);
+            new_note(&dir, filename);
         }
 
-        Nt::Search { needle } => {
-            // Use rg to search the nt directory
-            let rg_bytes = Command::new("rg")
-                .arg("--line-number")
-                .arg("--no-heading")
-                .arg("--fixed-strings")
-                .arg(needle)
-                .arg(dir.as_path().display().to_string())
-                .output()
-                .expect("rg to work")
-                .stdout;
-
-            // Pipe the results of rg to skim, the fzf library written in Rust
-            let bat_cmd = "bat --style=numbers --color=always --highlight-line {2} --line-range";
-            let awk_cmd = "(echo {2} | awk '{a=$1-5;if(a<0)a=0;print a}')";
-            let preview_cmd = format!("{} {}: {{1}} | head -n10", bat_cmd, awk_cmd);
-
-            let options = skim::prelude::SkimOptionsBuilder::default()
-                .height(Some("50%"))
-                .delimiter(Some(":"))
-                .multi(true)
-                .preview(Some(&preview_cmd))
-                .build()
-                .unwrap();
-
-            // `SkimItemReader` is a helper to turn any `BufRead` into a stream of `SkimItem`
-            // `SkimItem` was implemented for `AsRef<str>` by default
-            let item_reader = skim::prelude::SkimItemReader::default();
-            let items = item_reader.of_bufread(std::io::Cursor::new(rg_bytes));
-
-            // `run_with` would read and show items from the stream
-            let selected_items = skim::Skim::run_with(&options, Some(items))
-                .map(|out| out.selected_items)
-                .unwrap_or_else(|| Vec::new());
-
-            // For each item selected in Skim, open in editor
-            for item in selected_items.iter() {
-                let output = item.output();
-                let split: Vec<&str> = output.splitn(3, ':').collect();
-                let fullname = split[0];
-                let lineno = split[1];
-
-                let editor = edit::get_editor().expect("Editor should exist");
-
-                // We can open to the correct line in vim
-                match editor.as_path().display().to_string().as_ref() {
-                    "vim" => {
-                        Command::new(&editor)
-                            .args(&[format!("+{}", lineno)])
-                            .arg(&fullname)
-                            .stdin(Stdio::inherit())
-                            .stdout(Stdio::inherit())
-                            .stderr(Stdio::inherit())
-                            .output()
-                            .expect("vim should have worked")
-                            .status;
-                    }
-                    x => {
-                        edit::edit_file(Path::new(x)).expect("Edit should have worked");
-                    }
+        Nt::Search {
+            needle,
+            finder,
+            tag,
+        } => {
+            let searcher = Searcher::detect().unwrap_or_else(|err| {
+                eprintln!("nt: {}", err);
+                std::process::exit(1);
+            });
+            let previewer = Previewer::detect().unwrap_or_else(|err| {
+                eprintln!("nt: {}", err);
+                std::process::exit(1);
+            });
+            let preview_cmd = previewer.preview_cmd();
+            let finder = FinderChoice::resolve(finder).finder();
+
+            // Loop so `ctrl-n`/`ctrl-e` can act without leaving the session.
+            loop {
+                // A `--tag` narrows the search corpus to the notes carrying
+                // it; otherwise search the whole notes directory. Recomputed
+                // every iteration so a note tagged via `ctrl-n` mid-session
+                // shows up without restarting the search.
+                let targets: Vec<String> = match &tag {
+                    Some(tag) => tags::notes_with_tag(&dir, tag).into_iter().collect(),
+                    None => vec![dir.display().to_string()],
+                };
+                if targets.is_empty() {
+                    println!("No notes tagged `{}`", tag.as_deref().unwrap_or_default());
+                    break;
+                }
+
+                let search_bytes = searcher
+                    .command(&needle, &targets)
+                    .output()
+                    .expect("search tool to run")
+                    .stdout;
+
+                let result = finder.call(&preview_cmd, search_bytes);
+
+                if result.action == FinderAction::NewNote {
+                    new_note(&dir, None);
+                    continue;
+                }
+
+                for output in result.selected.iter() {
+                    let split: Vec<&str> = output.splitn(3, ':').collect();
+                    let fullname = split[0];
+                    let lineno = split[1];
+
+                    editor::open_at_line(fullname, lineno).expect("Editor should have worked");
+
+                    let mut path = std::path::PathBuf::new();
+                    path.push(fullname);
+                    print_preview(Action::Edited, &dir, &path);
+                }
+
+                if result.action == FinderAction::Edit {
+                    continue;
                 }
-                let mut path = std::path::PathBuf::new();
-                path.push(fullname);
-                print_preview(Action::Edited, &path);
+                break;
             }
         }
     }
@@ -0,0 +1,95 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// The on-disk tag index: a line-oriented `tag\tnote` file kept as a
+/// sibling of the notes directory (not inside it), so it never shows up
+/// as a bogus match in full-text search over the notes themselves.
+fn index_path(dir: &Path) -> PathBuf {
+    let name = dir.file_name().and_then(|n| n.to_str()).unwrap_or("nt");
+    match dir.parent() {
+        Some(parent) => parent.join(format!("{}.index", name)),
+        None => dir.join(format!("{}.index", name)),
+    }
+}
+
+/// The YAML front matter seeded onto every new note.
+pub fn front_matter(title: &str, created: &str) -> String {
+    format!(
+        "---\ntitle: {}\ncreated: {}\ntags: []\n---\n\n",
+        title, created
+    )
+}
+
+/// Extracts the `tags: [a, b, c]` line from a note's front matter, if any.
+pub fn parse_tags(contents: &str) -> Vec<String> {
+    let mut lines = contents.lines();
+    if lines.next() != Some("---") {
+        return Vec::new();
+    }
+
+    for line in lines {
+        if line == "---" {
+            break;
+        }
+        if let Some(rest) = line.strip_prefix("tags:") {
+            return rest
+                .trim()
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .split(',')
+                .map(|tag| tag.trim().to_string())
+                .filter(|tag| !tag.is_empty())
+                .collect();
+        }
+    }
+    Vec::new()
+}
+
+/// Re-reads `note`'s front matter and rewrites its entries in the tag
+/// index, dropping any tags the note no longer carries.
+pub fn reindex(dir: &Path, note: &Path) {
+    let contents = match fs::read_to_string(note) {
+        Ok(contents) => contents,
+        Err(_) => return,
+    };
+    let note = note.display().to_string();
+
+    let mut entries: Vec<(String, String)> = fs::read_to_string(index_path(dir))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, '\t');
+            Some((parts.next()?.to_string(), parts.next()?.to_string()))
+        })
+        .filter(|(_, existing)| existing != &note)
+        .collect();
+
+    for tag in parse_tags(&contents) {
+        entries.push((tag, note.clone()));
+    }
+
+    let mut file = fs::File::create(index_path(dir)).expect("tag index to be writable");
+    for (tag, note) in entries {
+        writeln!(file, "{}\t{}", tag, note).expect("tag index to be writable");
+    }
+}
+
+/// Notes carrying `tag`, looked up from the on-disk index.
+pub fn notes_with_tag(dir: &Path, tag: &str) -> BTreeSet<String> {
+    fs::read_to_string(index_path(dir))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, '\t');
+            let candidate = parts.next()?;
+            let note = parts.next()?;
+            if candidate == tag {
+                Some(note.to_string())
+            } else {
+                None
+            }
+        })
+        .collect()
+}